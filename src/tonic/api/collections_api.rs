@@ -1,27 +1,93 @@
+mod alias_access;
+mod idempotency;
+mod limits;
+mod operation_registry;
+mod streaming;
+
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use api::grpc::qdrant::collections_server::Collections;
 use api::grpc::qdrant::{
-    AliasDescription, ChangeAliases, CollectionOperationResponse, CreateCollection,
-    DeleteCollection, GetCollectionInfoRequest, GetCollectionInfoResponse, ListAliasesRequest,
-    ListAliasesResponse, ListCollectionAliasesRequest, ListCollectionsRequest,
-    ListCollectionsResponse, UpdateCollection,
+    AliasDescription, ChangeAliases, CollectionDescription, CollectionOperationResponse,
+    CreateCollection, DeleteCollection, GetCollectionInfoRequest, GetCollectionInfoResponse,
+    GetCollectionLimitsRequest, GetCollectionLimitsResponse, GetOperationStatusRequest,
+    GetOperationStatusResponse, ListAliasesRequest, ListAliasesResponse, ListAliasesStreamRequest,
+    ListCollectionAliasesRequest, ListCollectionsRequest, ListCollectionsResponse,
+    ListCollectionsStreamRequest, UpdateCollection,
+};
+use storage::content_manager::collection_meta_ops::{
+    AliasOperations, ChangeAliasesOperation, CollectionMetaOperations, DeleteAliasOperation,
 };
 use storage::content_manager::conversions::error_to_status;
 use storage::dispatcher::Dispatcher;
 use tonic::{Request, Response, Status};
+use uuid::Uuid;
 
 use crate::common::collections::*;
 use crate::tonic::api::collections_common::get;
+use alias_access::AliasAccessStore;
+use idempotency::{Claim, IdempotencyStore, OperationKind};
+use limits::CollectionLimits;
+use operation_registry::{OperationRegistry, OperationStatus};
+use streaming::{stream_paginated, ItemStream};
+
+/// How long a finished backgrounded operation stays queryable via `get_operation_status`
+/// before it is swept from the registry.
+const OPERATION_RETENTION: Duration = Duration::from_secs(3600);
+
+/// How often the alias reaper scans for aliases past their TTL.
+const ALIAS_REAPER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a cached idempotency-key result is replayed before a retry is treated as a new
+/// request.
+const IDEMPOTENCY_RETENTION: Duration = Duration::from_secs(600);
+
+/// Runtime-configurable knobs for [`CollectionsService`] beyond the dispatcher it wraps.
+#[derive(Clone, Debug, Default)]
+pub struct CollectionsServiceConfig {
+    /// Reap aliases unresolved for this long, as tracked by metadata-plane lookups only (see
+    /// [`CollectionsService::touch_alias`]). Opt-in: `None`/zero disables the sweep.
+    pub alias_ttl: Option<Duration>,
+    /// Guardrails consulted before `CreateCollection`/`UpdateCollection` reach consensus.
+    pub limits: CollectionLimits,
+}
 
 pub struct CollectionsService {
     dispatcher: Arc<Dispatcher>,
+    operations: Arc<OperationRegistry>,
+    alias_access: Arc<AliasAccessStore>,
+    idempotency: Arc<IdempotencyStore>,
+    limits: Arc<CollectionLimits>,
 }
 
 impl CollectionsService {
     pub fn new(dispatcher: Arc<Dispatcher>) -> Self {
-        Self { dispatcher }
+        Self::new_with_config(dispatcher, CollectionsServiceConfig::default())
+    }
+
+    pub fn new_with_config(dispatcher: Arc<Dispatcher>, config: CollectionsServiceConfig) -> Self {
+        let alias_access = Arc::new(AliasAccessStore::new());
+
+        if let Some(alias_ttl) = config.alias_ttl.filter(|ttl| !ttl.is_zero()) {
+            let dispatcher = dispatcher.clone();
+            let alias_access = alias_access.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(ALIAS_REAPER_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    reap_stale_aliases(&dispatcher, &alias_access, alias_ttl).await;
+                }
+            });
+        }
+
+        Self {
+            dispatcher,
+            operations: Arc::new(OperationRegistry::new(OPERATION_RETENTION)),
+            alias_access,
+            idempotency: Arc::new(IdempotencyStore::new(IDEMPOTENCY_RETENTION)),
+            limits: Arc::new(config.limits),
+        }
     }
 
     async fn perform_operation<O>(
@@ -30,6 +96,9 @@ impl CollectionsService {
     ) -> Result<Response<CollectionOperationResponse>, Status>
     where
         O: WithTimeout
+            + WithBackground
+            + WithIdempotencyKey
+            + WithGuardrails
             + TryInto<
                 storage::content_manager::collection_meta_ops::CollectionMetaOperations,
                 Error = Status,
@@ -37,29 +106,175 @@ impl CollectionsService {
     {
         let operation = request.into_inner();
         let wait_timeout = operation.wait_timeout();
+        let background = operation.is_background();
+        let idempotency_key = operation.idempotency_key();
+
+        // Background operations need an `operation_id` up front so the idempotency claim (made
+        // before any guardrail check or await) can point a racing retry at it; synchronous
+        // operations have nothing to claim, since there's no in-flight placeholder to reuse.
+        let operation_id = background.then(|| Uuid::new_v4().to_string());
+
+        if let Some(key) = &idempotency_key {
+            match self
+                .idempotency
+                .check_and_claim(O::KIND, key, operation_id.clone())
+            {
+                Claim::Done(cached) => return Ok(Response::new(cached)),
+                Claim::Pending(operation_id) => {
+                    let response = CollectionOperationResponse {
+                        operation_id: Some(operation_id),
+                        result: true,
+                        ..CollectionOperationResponse::default()
+                    };
+                    return Ok(Response::new(response));
+                }
+                Claim::Claimed => {}
+            }
+        }
+
+        let existing_collection_count = if O::CHECKS_COLLECTION_COUNT {
+            self.collect_collections().await.len()
+        } else {
+            0
+        };
+        if let Err(err) = operation.check_guardrails(&self.limits, existing_collection_count) {
+            if let Some(key) = &idempotency_key {
+                self.idempotency.clear_pending(O::KIND, key);
+            }
+            return Err(err);
+        }
+
+        let meta_op = match operation.try_into() {
+            Ok(meta_op) => meta_op,
+            Err(err) => {
+                if let Some(key) = &idempotency_key {
+                    self.idempotency.clear_pending(O::KIND, key);
+                }
+                return Err(err);
+            }
+        };
+
+        if background {
+            let operation_id = operation_id.expect("generated above when background");
+            self.operations.insert_pending(operation_id.clone());
+
+            let dispatcher = self.dispatcher.clone();
+            let operations = self.operations.clone();
+            let alias_access = self.alias_access.clone();
+            let idempotency = self.idempotency.clone();
+            let timing = Instant::now();
+            let spawned_id = operation_id.clone();
+            let alias_touched = meta_op.clone();
+            tokio::spawn(async move {
+                operations.set_running(&spawned_id);
+                match dispatcher
+                    .submit_collection_meta_op(meta_op, wait_timeout)
+                    .await
+                {
+                    Ok(result) => {
+                        stamp_alias_changes(&alias_touched, &alias_access);
+                        let response = CollectionOperationResponse::from((timing, result));
+                        if let Some(key) = idempotency_key {
+                            idempotency.complete(O::KIND, key, response.clone());
+                        }
+                        operations.set_completed(&spawned_id, response);
+                    }
+                    Err(err) => {
+                        if let Some(key) = idempotency_key {
+                            idempotency.clear_pending(O::KIND, &key);
+                        }
+                        operations.set_failed(&spawned_id, err.to_string());
+                    }
+                }
+                operations.sweep_expired();
+                idempotency.sweep_expired();
+            });
+
+            let response = CollectionOperationResponse {
+                operation_id: Some(operation_id),
+                result: true,
+                ..CollectionOperationResponse::default()
+            };
+            return Ok(Response::new(response));
+        }
+
+        let alias_touched = meta_op.clone();
         let timing = Instant::now();
         let result = self
             .dispatcher
-            .submit_collection_meta_op(operation.try_into()?, wait_timeout)
+            .submit_collection_meta_op(meta_op, wait_timeout)
             .await
             .map_err(error_to_status)?;
+        stamp_alias_changes(&alias_touched, &self.alias_access);
 
         let response = CollectionOperationResponse::from((timing, result));
+        if let Some(key) = idempotency_key {
+            self.idempotency.complete(O::KIND, key, response.clone());
+            self.idempotency.sweep_expired();
+        }
+        Ok(Response::new(response))
+    }
+
+    async fn get_operation_status(
+        &self,
+        request: Request<GetOperationStatusRequest>,
+    ) -> Result<Response<GetOperationStatusResponse>, Status> {
+        let GetOperationStatusRequest { operation_id } = request.into_inner();
+        let status = self
+            .operations
+            .get(&operation_id)
+            .ok_or_else(|| Status::not_found(format!("unknown operation_id {operation_id}")))?;
+
+        let response = match status {
+            OperationStatus::Pending => GetOperationStatusResponse {
+                operation_id,
+                pending: true,
+                ..Default::default()
+            },
+            OperationStatus::Running => GetOperationStatusResponse {
+                operation_id,
+                running: true,
+                ..Default::default()
+            },
+            OperationStatus::Completed(result) => GetOperationStatusResponse {
+                operation_id,
+                result: Some(result),
+                ..Default::default()
+            },
+            OperationStatus::Failed(error) => GetOperationStatusResponse {
+                operation_id,
+                error: Some(error),
+                ..Default::default()
+            },
+        };
         Ok(Response::new(response))
     }
 
+    fn with_last_accessed(&self, mut alias: AliasDescription) -> AliasDescription {
+        alias.last_accessed_secs_ago = self.alias_access.last_accessed_secs_ago(&alias.alias_name);
+        alias
+    }
+
+    async fn collect_aliases(&self) -> Result<Vec<AliasDescription>, Status> {
+        self.dispatcher
+            .toc()
+            .list_aliases()
+            .await
+            .map(|aliases| {
+                aliases
+                    .into_iter()
+                    .map(|alias| self.with_last_accessed(alias.into()))
+                    .collect()
+            })
+            .map_err(error_to_status)
+    }
+
     async fn list_aliases(
         &self,
         _request: Request<ListAliasesRequest>,
     ) -> Result<Response<ListAliasesResponse>, Status> {
         let timing = Instant::now();
-        let aliases = self
-            .dispatcher
-            .toc()
-            .list_aliases()
-            .await
-            .map(|aliases| aliases.into_iter().map(|alias| alias.into()).collect())
-            .map_err(error_to_status)?;
+        let aliases = self.collect_aliases().await?;
         let response = ListAliasesResponse {
             aliases,
             time: timing.elapsed().as_secs_f64(),
@@ -67,6 +282,69 @@ impl CollectionsService {
         Ok(Response::new(response))
     }
 
+    async fn list_aliases_stream(
+        &self,
+        request: Request<ListAliasesStreamRequest>,
+    ) -> Result<Response<ItemStream<AliasDescription>>, Status> {
+        let ListAliasesStreamRequest { filter, page_size } = request.into_inner();
+        let dispatcher = self.dispatcher.clone();
+        let alias_access = self.alias_access.clone();
+        let stream = stream_paginated(page_size, move |cursor, limit| {
+            let dispatcher = dispatcher.clone();
+            let alias_access = alias_access.clone();
+            let filter = filter.clone();
+            async move {
+                let (aliases, next_cursor) = dispatcher
+                    .toc()
+                    .list_aliases_page(filter.as_deref(), cursor.as_deref(), limit)
+                    .await
+                    .map_err(error_to_status)?;
+                let items = aliases
+                    .into_iter()
+                    .map(|alias| {
+                        let mut alias: AliasDescription = alias.into();
+                        alias.last_accessed_secs_ago =
+                            alias_access.last_accessed_secs_ago(&alias.alias_name);
+                        alias
+                    })
+                    .collect();
+                Ok((items, next_cursor))
+            }
+        });
+        Ok(Response::new(stream))
+    }
+
+    async fn collect_collections(&self) -> Vec<CollectionDescription> {
+        let timing = Instant::now();
+        let result = do_list_collections(&self.dispatcher).await;
+        ListCollectionsResponse::from((timing, result)).collections
+    }
+
+    async fn list_collections_stream(
+        &self,
+        request: Request<ListCollectionsStreamRequest>,
+    ) -> Result<Response<ItemStream<CollectionDescription>>, Status> {
+        let ListCollectionsStreamRequest { filter, page_size } = request.into_inner();
+        let dispatcher = self.dispatcher.clone();
+        let stream = stream_paginated(page_size, move |cursor, limit| {
+            let dispatcher = dispatcher.clone();
+            let filter = filter.clone();
+            async move {
+                let (names, next_cursor) = dispatcher
+                    .toc()
+                    .list_collections_page(filter.as_deref(), cursor.as_deref(), limit)
+                    .await
+                    .map_err(error_to_status)?;
+                let items = names
+                    .into_iter()
+                    .map(|name| CollectionDescription { name })
+                    .collect();
+                Ok((items, next_cursor))
+            }
+        });
+        Ok(Response::new(stream))
+    }
+
     async fn list_collection_aliases(
         &self,
         request: Request<ListCollectionAliasesRequest>,
@@ -81,9 +359,12 @@ impl CollectionsService {
             .map(|aliases| {
                 aliases
                     .into_iter()
-                    .map(|alias| AliasDescription {
-                        alias_name: alias,
-                        collection_name: collection_name.clone(),
+                    .map(|alias| {
+                        self.with_last_accessed(AliasDescription {
+                            alias_name: alias,
+                            collection_name: collection_name.clone(),
+                            last_accessed_secs_ago: None,
+                        })
                     })
                     .collect()
             })
@@ -94,15 +375,61 @@ impl CollectionsService {
         };
         Ok(Response::new(response))
     }
+
+    /// Stamp `collection_name` as accessed if it currently names an alias.
+    ///
+    /// This is metadata-plane tracking only: it runs on `GetCollectionInfo` lookups, not on the
+    /// data-plane resolution that `Search`/`Upsert`/`Scroll`/etc. use (that path lives in the
+    /// points API, outside this service). An alias served exclusively by data-plane traffic and
+    /// never looked up via `GetCollectionInfo` will still age out under `reap_stale_aliases`;
+    /// callers relying on the TTL reaper to spare live-but-metadata-quiet aliases should disable
+    /// `alias_ttl` or extend the data-plane resolution path to call `AliasAccessStore::accessed`
+    /// directly.
+    async fn touch_alias(&self, collection_name: &str) -> Result<(), Status> {
+        let is_alias = self
+            .collect_aliases()
+            .await?
+            .iter()
+            .any(|alias| alias.alias_name == collection_name);
+        if is_alias {
+            self.alias_access.accessed(collection_name);
+        }
+        Ok(())
+    }
+
+    async fn get_collection_limits(
+        &self,
+        _request: Request<GetCollectionLimitsRequest>,
+    ) -> Result<Response<GetCollectionLimitsResponse>, Status> {
+        let response = GetCollectionLimitsResponse {
+            max_vector_dim: self.limits.max_vector_dim,
+            max_collections: self.limits.max_collections.map(|limit| limit as u64),
+            max_shards_per_collection: self.limits.max_shards_per_collection,
+            max_replication_factor: self.limits.max_replication_factor,
+            allowed_distances: self
+                .limits
+                .allowed_distances
+                .iter()
+                .flatten()
+                .map(|distance| *distance as i32)
+                .collect(),
+        };
+        Ok(Response::new(response))
+    }
 }
 
 #[tonic::async_trait]
 impl Collections for CollectionsService {
+    type ListAliasesStreamStream = ItemStream<AliasDescription>;
+    type ListCollectionsStreamStream = ItemStream<CollectionDescription>;
+
     async fn get(
         &self,
         request: Request<GetCollectionInfoRequest>,
     ) -> Result<Response<GetCollectionInfoResponse>, Status> {
-        get(self.dispatcher.as_ref(), request.into_inner(), None).await
+        let request = request.into_inner();
+        self.touch_alias(&request.collection_name).await?;
+        get(self.dispatcher.as_ref(), request, None).await
     }
 
     async fn list(
@@ -157,12 +484,157 @@ impl Collections for CollectionsService {
     ) -> Result<Response<ListAliasesResponse>, Status> {
         self.list_aliases(request).await
     }
+
+    async fn get_operation_status(
+        &self,
+        request: Request<GetOperationStatusRequest>,
+    ) -> Result<Response<GetOperationStatusResponse>, Status> {
+        self.get_operation_status(request).await
+    }
+
+    async fn list_aliases_stream(
+        &self,
+        request: Request<ListAliasesStreamRequest>,
+    ) -> Result<Response<Self::ListAliasesStreamStream>, Status> {
+        self.list_aliases_stream(request).await
+    }
+
+    async fn list_collections_stream(
+        &self,
+        request: Request<ListCollectionsStreamRequest>,
+    ) -> Result<Response<Self::ListCollectionsStreamStream>, Status> {
+        self.list_collections_stream(request).await
+    }
+
+    async fn get_collection_limits(
+        &self,
+        request: Request<GetCollectionLimitsRequest>,
+    ) -> Result<Response<GetCollectionLimitsResponse>, Status> {
+        self.get_collection_limits(request).await
+    }
+}
+
+/// Stamp or clear alias access tracking for the aliases touched by a just-applied meta-op,
+/// so newly created aliases start their TTL clock and removed aliases stop being tracked.
+fn stamp_alias_changes(meta_op: &CollectionMetaOperations, alias_access: &AliasAccessStore) {
+    let CollectionMetaOperations::ChangeAliases(ChangeAliasesOperation { actions }) = meta_op
+    else {
+        return;
+    };
+    for action in actions {
+        match action {
+            AliasOperations::CreateAlias(op) => alias_access.accessed(&op.alias_name),
+            AliasOperations::RenameAlias(op) => {
+                alias_access.remove(&op.old_alias_name);
+                alias_access.accessed(&op.new_alias_name);
+            }
+            AliasOperations::DeleteAlias(op) => alias_access.remove(&op.alias_name),
+        }
+    }
+}
+
+/// Find aliases whose last access is older than `alias_ttl` and remove them through the normal
+/// `ChangeAliases` consensus path, the same way an operator-initiated `DeleteAlias` would be.
+async fn reap_stale_aliases(
+    dispatcher: &Dispatcher,
+    alias_access: &AliasAccessStore,
+    alias_ttl: Duration,
+) {
+    for alias_name in alias_access.older_aliases(alias_ttl) {
+        // Re-check right before submitting: the alias may have been touched again in the time
+        // between this snapshot and now, especially since consensus submission can be slow.
+        if !alias_access.is_stale(&alias_name, alias_ttl) {
+            continue;
+        }
+
+        let op = CollectionMetaOperations::ChangeAliases(ChangeAliasesOperation {
+            actions: vec![AliasOperations::DeleteAlias(DeleteAliasOperation {
+                alias_name: alias_name.clone(),
+            })],
+        });
+        match dispatcher.submit_collection_meta_op(op, None).await {
+            // The alias may already be gone if a concurrent sweep or explicit delete won the
+            // race; either way it should stop being tracked, so treat this as a success.
+            Ok(_) | Err(storage::content_manager::errors::StorageError::NotFound { .. }) => {
+                alias_access.remove(&alias_name);
+            }
+            Err(err) => {
+                log::warn!("failed to reap stale alias {alias_name}: {err}");
+            }
+        }
+    }
 }
 
 trait WithTimeout {
     fn wait_timeout(&self) -> Option<Duration>;
 }
 
+/// Whether a meta-operation should be dispatched without waiting for it to finish.
+/// Defaults to `false` so existing clients keep the synchronous behavior they rely on.
+trait WithBackground {
+    fn is_background(&self) -> bool;
+}
+
+/// A meta-operation's client-supplied idempotency key, scoped to its `OperationKind` so the
+/// same key reused across different operation types never cross-matches.
+trait WithIdempotencyKey {
+    const KIND: OperationKind;
+
+    fn idempotency_key(&self) -> Option<String>;
+}
+
+/// Guardrail check consulted before a meta-operation is submitted to consensus.
+trait WithGuardrails {
+    /// Whether `check_guardrails` needs the cluster's current collection count. Only
+    /// `CreateCollection` does, so `perform_operation` skips that listing call otherwise.
+    const CHECKS_COLLECTION_COUNT: bool = false;
+
+    fn check_guardrails(
+        &self,
+        limits: &CollectionLimits,
+        existing_collection_count: usize,
+    ) -> Result<(), Status>;
+}
+
+impl WithGuardrails for CreateCollection {
+    const CHECKS_COLLECTION_COUNT: bool = true;
+
+    fn check_guardrails(
+        &self,
+        limits: &CollectionLimits,
+        existing_collection_count: usize,
+    ) -> Result<(), Status> {
+        limits.check_create(self, existing_collection_count)
+    }
+}
+
+impl WithGuardrails for UpdateCollection {
+    fn check_guardrails(
+        &self,
+        limits: &CollectionLimits,
+        _existing_collection_count: usize,
+    ) -> Result<(), Status> {
+        limits.check_update(self)
+    }
+}
+
+macro_rules! impl_with_guardrails_noop {
+    ($operation:ty) => {
+        impl WithGuardrails for $operation {
+            fn check_guardrails(
+                &self,
+                _limits: &CollectionLimits,
+                _existing_collection_count: usize,
+            ) -> Result<(), Status> {
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_with_guardrails_noop!(DeleteCollection);
+impl_with_guardrails_noop!(ChangeAliases);
+
 macro_rules! impl_with_timeout {
     ($operation:ty) => {
         impl WithTimeout for $operation {
@@ -173,7 +645,39 @@ macro_rules! impl_with_timeout {
     };
 }
 
+macro_rules! impl_with_background {
+    ($operation:ty) => {
+        impl WithBackground for $operation {
+            fn is_background(&self) -> bool {
+                self.background.unwrap_or(false)
+            }
+        }
+    };
+}
+
+macro_rules! impl_with_idempotency_key {
+    ($operation:ty, $kind:expr) => {
+        impl WithIdempotencyKey for $operation {
+            const KIND: OperationKind = $kind;
+
+            fn idempotency_key(&self) -> Option<String> {
+                self.idempotency_key.clone()
+            }
+        }
+    };
+}
+
 impl_with_timeout!(CreateCollection);
 impl_with_timeout!(UpdateCollection);
 impl_with_timeout!(DeleteCollection);
 impl_with_timeout!(ChangeAliases);
+
+impl_with_background!(CreateCollection);
+impl_with_background!(UpdateCollection);
+impl_with_background!(DeleteCollection);
+impl_with_background!(ChangeAliases);
+
+impl_with_idempotency_key!(CreateCollection, OperationKind::Create);
+impl_with_idempotency_key!(UpdateCollection, OperationKind::Update);
+impl_with_idempotency_key!(DeleteCollection, OperationKind::Delete);
+impl_with_idempotency_key!(ChangeAliases, OperationKind::ChangeAliases);
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Tracks the last time each alias was resolved, so abandoned aliases can be reaped on a TTL.
+#[derive(Default)]
+pub struct AliasAccessStore {
+    last_accessed: Mutex<HashMap<String, Instant>>,
+}
+
+impl AliasAccessStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stamp `alias` as accessed right now.
+    pub fn accessed(&self, alias: &str) {
+        self.last_accessed
+            .lock()
+            .unwrap()
+            .insert(alias.to_owned(), Instant::now());
+    }
+
+    /// Last-access time for `alias`, in seconds since it was stamped, if known.
+    pub fn last_accessed_secs_ago(&self, alias: &str) -> Option<f64> {
+        self.last_accessed
+            .lock()
+            .unwrap()
+            .get(alias)
+            .map(|instant| instant.elapsed().as_secs_f64())
+    }
+
+    /// Aliases whose last access is older than `cutoff`.
+    pub fn older_aliases(&self, cutoff: Duration) -> Vec<String> {
+        self.last_accessed
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, instant)| instant.elapsed() >= cutoff)
+            .map(|(alias, _)| alias.clone())
+            .collect()
+    }
+
+    /// Whether `alias` is still older than `cutoff` right now. Used to re-check a reap candidate
+    /// immediately before deleting it, since time passes between snapshotting candidates via
+    /// [`Self::older_aliases`] and actually submitting the delete through consensus.
+    pub fn is_stale(&self, alias: &str, cutoff: Duration) -> bool {
+        self.last_accessed
+            .lock()
+            .unwrap()
+            .get(alias)
+            .map(|instant| instant.elapsed() >= cutoff)
+            .unwrap_or(false)
+    }
+
+    /// Drop tracking for `alias`, e.g. once it has been removed. Idempotent.
+    pub fn remove(&self, alias: &str) {
+        self.last_accessed.lock().unwrap().remove(alias);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn older_aliases_respects_cutoff() {
+        let store = AliasAccessStore::new();
+        store.accessed("fresh");
+        assert!(store
+            .older_aliases(Duration::from_secs(0))
+            .contains(&"fresh".to_owned()));
+        assert!(store.older_aliases(Duration::from_secs(3600)).is_empty());
+    }
+
+    #[test]
+    fn is_stale_reflects_a_fresh_touch() {
+        let store = AliasAccessStore::new();
+        store.accessed("alias");
+        assert!(!store.is_stale("alias", Duration::from_secs(3600)));
+        assert!(store.is_stale("alias", Duration::from_secs(0)));
+    }
+
+    #[test]
+    fn remove_drops_tracking() {
+        let store = AliasAccessStore::new();
+        store.accessed("alias");
+        store.remove("alias");
+        assert_eq!(store.last_accessed_secs_ago("alias"), None);
+    }
+}
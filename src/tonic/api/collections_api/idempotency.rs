@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use api::grpc::qdrant::CollectionOperationResponse;
+
+/// Which family of meta-operation an idempotency key was claimed against, so a `CreateCollection`
+/// and a `DeleteCollection` reusing the same key by accident never collide.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OperationKind {
+    Create,
+    Update,
+    Delete,
+    ChangeAliases,
+}
+
+enum Entry {
+    Pending {
+        operation_id: String,
+    },
+    Done {
+        response: CollectionOperationResponse,
+        recorded_at: Instant,
+    },
+}
+
+/// Outcome of [`IdempotencyStore::check_and_claim`].
+pub enum Claim {
+    /// A terminal result was already recorded for this key; replay it, don't re-execute.
+    Done(CollectionOperationResponse),
+    /// A background operation is already in flight for this key; don't spawn another one.
+    Pending(String),
+    /// No prior record existed (or it expired). If an `operation_id` was supplied, it is now
+    /// claimed as `Pending` and must be resolved via `complete`/`clear_pending`.
+    Claimed,
+}
+
+/// Caches the result of a meta-operation by client-supplied `idempotency_key`, so a retried
+/// request with the same key replays the original result (or the in-flight `operation_id`)
+/// instead of re-executing.
+pub struct IdempotencyStore {
+    entries: Mutex<HashMap<(OperationKind, String), Entry>>,
+    retention: Duration,
+}
+
+impl IdempotencyStore {
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            retention,
+        }
+    }
+
+    /// Atomically check `(kind, key)`'s current state and, if it has none (or an expired `Done`
+    /// result), claim it as `Pending` for `operation_id` — all under a single lock acquisition,
+    /// so two concurrent callers racing on the same key can never both observe "unclaimed".
+    /// Pass `operation_id: None` for a synchronous (non-background) caller that has nothing to
+    /// claim; it only ever gets `Done`/`Pending` lookups, never inserts a claim.
+    pub fn check_and_claim(
+        &self,
+        kind: OperationKind,
+        key: &str,
+        operation_id: Option<String>,
+    ) -> Claim {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(&(kind, key.to_owned())) {
+            Some(Entry::Pending { operation_id }) => return Claim::Pending(operation_id.clone()),
+            Some(Entry::Done {
+                response,
+                recorded_at,
+            }) if recorded_at.elapsed() < self.retention => return Claim::Done(response.clone()),
+            Some(Entry::Done { .. }) | None => {}
+        }
+
+        match operation_id {
+            Some(operation_id) => {
+                entries.insert((kind, key.to_owned()), Entry::Pending { operation_id });
+            }
+            None => {
+                entries.remove(&(kind, key.to_owned()));
+            }
+        }
+        Claim::Claimed
+    }
+
+    /// Replace a claim (or record a synchronous result) with its terminal response.
+    pub fn complete(
+        &self,
+        kind: OperationKind,
+        key: String,
+        response: CollectionOperationResponse,
+    ) {
+        self.entries.lock().unwrap().insert(
+            (kind, key),
+            Entry::Done {
+                response,
+                recorded_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop a claim that never reached a terminal state (the background op failed, or the
+    /// request was rejected before it could be spawned), so a retry is free to re-attempt rather
+    /// than being told to wait on a claim that will never resolve.
+    pub fn clear_pending(&self, kind: OperationKind, key: &str) {
+        self.entries.lock().unwrap().remove(&(kind, key.to_owned()));
+    }
+
+    /// Drop terminal entries older than the retention window, bounding store growth. Pending
+    /// claims are left alone; they are replaced by `complete`/`clear_pending` once resolved.
+    pub fn sweep_expired(&self) {
+        let retention = self.retention;
+        self.entries.lock().unwrap().retain(|_, entry| match entry {
+            Entry::Pending { .. } => true,
+            Entry::Done { recorded_at, .. } => recorded_at.elapsed() < retention,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claim_is_visible_to_a_racing_caller() {
+        let store = IdempotencyStore::new(Duration::from_secs(60));
+        assert!(matches!(
+            store.check_and_claim(OperationKind::Create, "key", Some("op-1".to_owned())),
+            Claim::Claimed
+        ));
+        assert!(matches!(
+            store.check_and_claim(OperationKind::Create, "key", Some("op-2".to_owned())),
+            Claim::Pending(operation_id) if operation_id == "op-1"
+        ));
+    }
+
+    #[test]
+    fn complete_replaces_pending_claim() {
+        let store = IdempotencyStore::new(Duration::from_secs(60));
+        store.check_and_claim(OperationKind::Create, "key", Some("op-1".to_owned()));
+        store.complete(
+            OperationKind::Create,
+            "key".to_owned(),
+            CollectionOperationResponse::default(),
+        );
+        assert!(matches!(
+            store.check_and_claim(OperationKind::Create, "key", Some("op-2".to_owned())),
+            Claim::Done(_)
+        ));
+    }
+
+    #[test]
+    fn clear_pending_frees_the_key_for_retry() {
+        let store = IdempotencyStore::new(Duration::from_secs(60));
+        store.check_and_claim(OperationKind::Create, "key", Some("op-1".to_owned()));
+        store.clear_pending(OperationKind::Create, "key");
+        assert!(matches!(
+            store.check_and_claim(OperationKind::Create, "key", Some("op-2".to_owned())),
+            Claim::Claimed
+        ));
+    }
+
+    #[test]
+    fn sweep_expired_drops_old_done_entries_but_keeps_pending() {
+        let store = IdempotencyStore::new(Duration::from_secs(0));
+        store.check_and_claim(OperationKind::Create, "pending", Some("op-1".to_owned()));
+        store.complete(
+            OperationKind::Create,
+            "done".to_owned(),
+            CollectionOperationResponse::default(),
+        );
+        store.sweep_expired();
+        assert!(matches!(
+            store.check_and_claim(OperationKind::Create, "done", Some("op-2".to_owned())),
+            Claim::Claimed
+        ));
+        assert!(matches!(
+            store.check_and_claim(OperationKind::Create, "pending", Some("op-3".to_owned())),
+            Claim::Pending(operation_id) if operation_id == "op-1"
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+use api::grpc::qdrant::{vectors_config, CreateCollection, Distance, UpdateCollection};
+use tonic::Status;
+
+/// Operator-configured guardrails consulted by `perform_operation` before a `CreateCollection`
+/// or `UpdateCollection` request is submitted to consensus. A `None` limit means "unbounded".
+///
+/// There is deliberately no `max_payload_indexes` here: payload indexes are created through a
+/// separate field-index RPC, not through `CreateCollection`/`UpdateCollection`, so neither
+/// message carries anything this guardrail layer could check it against. Enforcing it belongs
+/// next to that RPC, not in this service.
+#[derive(Clone, Debug, Default)]
+pub struct CollectionLimits {
+    pub max_vector_dim: Option<u64>,
+    pub max_collections: Option<usize>,
+    pub max_shards_per_collection: Option<u32>,
+    pub max_replication_factor: Option<u32>,
+    pub allowed_distances: Option<Vec<Distance>>,
+}
+
+impl CollectionLimits {
+    /// Reject `request` with a precise `failed_precondition` naming the first limit it violates.
+    /// A vector whose distance value this build does not recognize is also rejected with
+    /// `failed_precondition`, whether or not any limit is configured.
+    pub fn check_create(
+        &self,
+        request: &CreateCollection,
+        existing_collection_count: usize,
+    ) -> Result<(), Status> {
+        if let Some(max_collections) = self.max_collections {
+            if existing_collection_count >= max_collections {
+                return Err(Status::failed_precondition(format!(
+                    "limit exceeded: cluster already has {existing_collection_count} collections, max_collections is {max_collections}"
+                )));
+            }
+        }
+
+        if let (Some(max_shards), Some(shard_number)) =
+            (self.max_shards_per_collection, request.shard_number)
+        {
+            if shard_number > max_shards {
+                return Err(Status::failed_precondition(format!(
+                    "limit exceeded: requested {shard_number} shards, max_shards_per_collection is {max_shards}"
+                )));
+            }
+        }
+
+        for (vector_name, size, distance) in named_vector_params(request)? {
+            if let Some(max_dim) = self.max_vector_dim {
+                if size > max_dim {
+                    return Err(Status::failed_precondition(format!(
+                        "limit exceeded: vector {vector_name:?} has dimension {size}, max_vector_dim is {max_dim}"
+                    )));
+                }
+            }
+
+            if let Some(allowed) = &self.allowed_distances {
+                if !allowed.contains(&distance) {
+                    return Err(Status::failed_precondition(format!(
+                        "limit exceeded: distance {distance:?} is not an allowed distance metric"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reject `request` if it would grow a collection's `replication_factor` past
+    /// `max_replication_factor`. This is the only part of `UpdateCollection` that changes how
+    /// many shard replicas the cluster has to host; `shard_number` itself is immutable once a
+    /// collection is created, so it's only checked in `check_create`.
+    pub fn check_update(&self, request: &UpdateCollection) -> Result<(), Status> {
+        let (Some(max_replication_factor), Some(replication_factor)) = (
+            self.max_replication_factor,
+            request
+                .params
+                .as_ref()
+                .and_then(|params| params.replication_factor),
+        ) else {
+            return Ok(());
+        };
+
+        if replication_factor > max_replication_factor {
+            return Err(Status::failed_precondition(format!(
+                "limit exceeded: requested replication_factor {replication_factor}, max_replication_factor is {max_replication_factor}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Every named vector's dimension and distance metric requested by `request`, regardless of
+/// whether it uses the single-vector or multi-vector config shape. Fails closed on a distance
+/// value this build doesn't recognize, rather than silently treating it as a default.
+fn named_vector_params(
+    request: &CreateCollection,
+) -> Result<Vec<(Option<String>, u64, Distance)>, Status> {
+    let to_distance = |raw: i32| {
+        Distance::from_i32(raw).ok_or_else(|| {
+            Status::failed_precondition(format!("unrecognized distance metric {raw}"))
+        })
+    };
+
+    match request
+        .vectors_config
+        .as_ref()
+        .and_then(|c| c.config.clone())
+    {
+        Some(vectors_config::Config::Params(params)) => {
+            Ok(vec![(None, params.size, to_distance(params.distance)?)])
+        }
+        Some(vectors_config::Config::ParamsMap(params_map)) => params_map
+            .map
+            .into_iter()
+            .map(|(name, params)| Ok((Some(name), params.size, to_distance(params.distance)?)))
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::grpc::qdrant::CollectionParamsDiff;
+
+    use super::*;
+
+    #[test]
+    fn check_create_rejects_over_shard_limit() {
+        let limits = CollectionLimits {
+            max_shards_per_collection: Some(2),
+            ..Default::default()
+        };
+        let request = CreateCollection {
+            shard_number: Some(4),
+            ..Default::default()
+        };
+        assert!(limits.check_create(&request, 0).is_err());
+    }
+
+    #[test]
+    fn check_create_rejects_unrecognized_distance() {
+        let limits = CollectionLimits::default();
+        let request = CreateCollection {
+            vectors_config: Some(api::grpc::qdrant::VectorsConfig {
+                config: Some(vectors_config::Config::Params(
+                    api::grpc::qdrant::VectorParams {
+                        size: 4,
+                        distance: 99,
+                        ..Default::default()
+                    },
+                )),
+            }),
+            ..Default::default()
+        };
+        assert!(limits.check_create(&request, 0).is_err());
+    }
+
+    #[test]
+    fn check_update_rejects_over_replication_limit() {
+        let limits = CollectionLimits {
+            max_replication_factor: Some(2),
+            ..Default::default()
+        };
+        let request = UpdateCollection {
+            params: Some(CollectionParamsDiff {
+                replication_factor: Some(5),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(limits.check_update(&request).is_err());
+    }
+
+    #[test]
+    fn check_update_allows_unset_replication_factor() {
+        let limits = CollectionLimits {
+            max_replication_factor: Some(2),
+            ..Default::default()
+        };
+        let request = UpdateCollection::default();
+        assert!(limits.check_update(&request).is_ok());
+    }
+}
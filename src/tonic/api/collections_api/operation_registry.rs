@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use api::grpc::qdrant::CollectionOperationResponse;
+
+/// Status of a backgrounded collection meta-operation, as tracked by [`OperationRegistry`].
+#[derive(Clone, Debug)]
+pub enum OperationStatus {
+    Pending,
+    Running,
+    Completed(CollectionOperationResponse),
+    Failed(String),
+}
+
+struct Entry {
+    status: OperationStatus,
+    finished_at: Option<Instant>,
+}
+
+/// In-memory registry mapping `operation_id` to the status of a backgrounded meta-operation.
+pub struct OperationRegistry {
+    entries: Mutex<HashMap<String, Entry>>,
+    retention: Duration,
+}
+
+impl OperationRegistry {
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            retention,
+        }
+    }
+
+    pub fn insert_pending(&self, operation_id: String) {
+        self.entries.lock().unwrap().insert(
+            operation_id,
+            Entry {
+                status: OperationStatus::Pending,
+                finished_at: None,
+            },
+        );
+    }
+
+    pub fn set_running(&self, operation_id: &str) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(operation_id) {
+            entry.status = OperationStatus::Running;
+        }
+    }
+
+    pub fn set_completed(&self, operation_id: &str, response: CollectionOperationResponse) {
+        self.finish(operation_id, OperationStatus::Completed(response));
+    }
+
+    pub fn set_failed(&self, operation_id: &str, error: String) {
+        self.finish(operation_id, OperationStatus::Failed(error));
+    }
+
+    fn finish(&self, operation_id: &str, status: OperationStatus) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(operation_id) {
+            entry.status = status;
+            entry.finished_at = Some(Instant::now());
+        }
+    }
+
+    pub fn get(&self, operation_id: &str) -> Option<OperationStatus> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(operation_id)
+            .map(|entry| entry.status.clone())
+    }
+
+    /// Drop terminal entries older than `retention`, bounding registry growth.
+    pub fn sweep_expired(&self) {
+        let retention = self.retention;
+        self.entries.lock().unwrap().retain(|_, entry| {
+            entry
+                .finished_at
+                .map(|finished_at| finished_at.elapsed() < retention)
+                .unwrap_or(true)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_then_running_then_completed() {
+        let registry = OperationRegistry::new(Duration::from_secs(60));
+        registry.insert_pending("op".to_owned());
+        assert!(matches!(registry.get("op"), Some(OperationStatus::Pending)));
+
+        registry.set_running("op");
+        assert!(matches!(registry.get("op"), Some(OperationStatus::Running)));
+
+        registry.set_completed("op", CollectionOperationResponse::default());
+        assert!(matches!(
+            registry.get("op"),
+            Some(OperationStatus::Completed(_))
+        ));
+    }
+
+    #[test]
+    fn sweep_expired_drops_old_terminal_entries() {
+        let registry = OperationRegistry::new(Duration::from_secs(0));
+        registry.insert_pending("op".to_owned());
+        registry.set_failed("op", "boom".to_owned());
+        registry.sweep_expired();
+        assert!(registry.get("op").is_none());
+    }
+
+    #[test]
+    fn sweep_expired_keeps_pending_entries() {
+        let registry = OperationRegistry::new(Duration::from_secs(0));
+        registry.insert_pending("op".to_owned());
+        registry.sweep_expired();
+        assert!(matches!(registry.get("op"), Some(OperationStatus::Pending)));
+    }
+}
@@ -0,0 +1,88 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use futures::Stream;
+use tonic::Status;
+
+/// Default page size used when a streaming request does not specify one.
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+pub type ItemStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Stream items sourced one page at a time from `fetch_page(cursor, limit)`, sending each page's
+/// items as soon as they arrive rather than materializing the full result set up front.
+/// `fetch_page` returns the page's items plus the cursor to resume from, or `None` once exhausted.
+pub fn stream_paginated<T, F, Fut>(page_size: Option<u32>, fetch_page: F) -> ItemStream<T>
+where
+    T: Send + 'static,
+    F: Fn(Option<String>, usize) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>), Status>> + Send,
+{
+    let page_size = page_size
+        .map(|size| size as usize)
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .max(1);
+    let (tx, rx) = tokio::sync::mpsc::channel(page_size);
+
+    tokio::spawn(async move {
+        let mut cursor = None;
+        loop {
+            let (items, next_cursor) = match fetch_page(cursor, page_size).await {
+                Ok(page) => page,
+                Err(err) => {
+                    let _ = tx.send(Err(err)).await;
+                    return;
+                }
+            };
+
+            for item in items {
+                if tx.send(Ok(item)).await.is_err() {
+                    // Client dropped the stream; stop fetching further pages.
+                    return;
+                }
+            }
+
+            match next_cursor {
+                Some(cursor_value) => cursor = Some(cursor_value),
+                None => return,
+            }
+        }
+    });
+
+    Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn streams_all_pages_in_order() {
+        let pages = vec![vec![1, 2], vec![3, 4], vec![5]];
+        let stream = stream_paginated(Some(2), move |cursor: Option<String>, _limit| {
+            let pages = pages.clone();
+            async move {
+                let index: usize = cursor.as_deref().unwrap_or("0").parse().unwrap();
+                let items = pages.get(index).cloned().unwrap_or_default();
+                let next_cursor = (index + 1 < pages.len()).then(|| (index + 1).to_string());
+                Ok((items, next_cursor))
+            }
+        });
+
+        let items: Vec<i32> = stream.map(|item| item.unwrap()).collect().await;
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn propagates_fetch_errors() {
+        let stream = stream_paginated::<i32, _, _>(None, |_cursor: Option<String>, _limit| async {
+            Err(Status::internal("boom"))
+        });
+
+        let items: Vec<_> = stream.collect().await;
+        assert_eq!(items.len(), 1);
+        assert!(items[0].is_err());
+    }
+}